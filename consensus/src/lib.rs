@@ -79,9 +79,9 @@ pub use segwit::{SegwitError, Witness, WitnessProgram, WitnessScript, WitnessVer
 pub use sigtypes::{Bip340Sig, LegacySig, SigError, SighashFlag, SighashType};
 pub use taproot::{
     ControlBlock, FutureLeafVer, InternalPk, IntoTapHash, InvalidLeafVer, InvalidParityValue,
-    LeafScript, LeafVer, OutputPk, Parity, TapBranchHash, TapCode, TapLeafHash, TapMerklePath,
-    TapNodeHash, TapScript, XOnlyPk, MIDSTATE_TAPSIGHASH, TAPROOT_ANNEX_PREFIX, TAPROOT_LEAF_MASK,
-    TAPROOT_LEAF_TAPSCRIPT,
+    InvalidTree, LeafInfo, LeafScript, LeafVer, OutputPk, Parity, TapBranchHash, TapCode,
+    TapLeafHash, TapMerklePath, TapNodeHash, TapScript, TapTree, XOnlyPk, MIDSTATE_TAPSIGHASH,
+    TAPROOT_ANNEX_PREFIX, TAPROOT_LEAF_MASK, TAPROOT_LEAF_TAPSCRIPT, TAPROOT_MAX_DEPTH,
 };
 pub use timelocks::{
     InvalidTimelock, LockHeight, LockTime, LockTimestamp, SeqNo, TimelockParseError,
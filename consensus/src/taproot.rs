@@ -27,14 +27,14 @@ use std::ops::BitXor;
 use std::str::FromStr;
 use std::{cmp, io, slice, vec};
 
-use amplify::confinement::Confined;
+use amplify::confinement::{Confined, NonEmptyVec, U32};
 use amplify::hex::FromHex;
 use amplify::{confinement, Bytes32, Wrapper};
 use commit_verify::{DigestExt, Sha256};
 use secp256k1::{PublicKey, Scalar, XOnlyPublicKey};
 use strict_encoding::{
-    DecodeError, ReadTuple, StrictDecode, StrictEncode, StrictProduct, StrictTuple, StrictType,
-    TypeName, TypedRead, TypedWrite, WriteTuple,
+    DecodeError, ReadTuple, StrictDecode, StrictDeserialize, StrictEncode, StrictProduct,
+    StrictSerialize, StrictTuple, StrictType, TypeName, TypedRead, TypedWrite, WriteTuple,
 };
 
 use crate::opcodes::*;
@@ -770,3 +770,275 @@ impl ControlBlock {
         }
     }
 }
+
+/// Maximal depth of a taproot script tree, defined by BIP-341.
+pub const TAPROOT_MAX_DEPTH: u8 = 128;
+
+/// Leaf of a taproot script tree together with its depth in the tree.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_BITCOIN)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct LeafInfo {
+    /// Depth of the leaf in the tree; zero for a tree consisting of a single
+    /// leaf.
+    pub depth: u8,
+    /// Leaf script.
+    pub script: LeafScript,
+}
+
+impl LeafInfo {
+    pub fn with(depth: u8, script: LeafScript) -> Self { LeafInfo { depth, script } }
+
+    pub fn tap_script(depth: u8, script: TapScript) -> Self {
+        LeafInfo::with(depth, LeafScript::from_tap_script(script))
+    }
+}
+
+/// Errors constructing taproot script tree from a list of leaves.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum InvalidTree {
+    /// taproot script tree must contain at least one leaf.
+    Empty,
+
+    /// leaf #{0} has depth {1} exceeding the taproot limit of 128.
+    TooDeep(usize, u8),
+
+    /// leaf #{0} doesn't fit into the tree, which is already complete.
+    Overfull(usize),
+
+    /// leaf depths don't form a complete binary tree.
+    Incomplete,
+
+    /// tree with {0} leaves exceeds the limit of 2^32-1 leaves.
+    TooManyLeaves(usize),
+}
+
+/// Taproot script tree, represented by its leaves in depth-first order (as in
+/// PSBT taproot tree serialization).
+///
+/// The construction guarantees that the leaf depths form a complete binary
+/// tree no deeper than [`TAPROOT_MAX_DEPTH`]; strict and serde deserialization
+/// perform the same validation.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode)]
+#[strict_type(lib = LIB_NAME_BITCOIN, dumb = { Self::with_single_leaf(LeafScript::default()) })]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", try_from = "Vec<LeafInfo>", into = "Vec<LeafInfo>")
+)]
+pub struct TapTree(NonEmptyVec<LeafInfo, U32>);
+
+impl StrictDecode for TapTree {
+    fn strict_decode(reader: &mut impl TypedRead) -> Result<Self, DecodeError> {
+        reader.read_tuple(|r| {
+            let leaves: NonEmptyVec<LeafInfo, U32> = r.read_field()?;
+            Self::from_leaves(leaves.into_inner())
+                .map_err(|err| DecodeError::DataIntegrityError(err.to_string()))
+        })
+    }
+}
+
+impl StrictSerialize for TapTree {}
+impl StrictDeserialize for TapTree {}
+
+impl TryFrom<Vec<LeafInfo>> for TapTree {
+    type Error = InvalidTree;
+
+    fn try_from(leaves: Vec<LeafInfo>) -> Result<Self, Self::Error> { Self::from_leaves(leaves) }
+}
+
+impl From<TapTree> for Vec<LeafInfo> {
+    fn from(tree: TapTree) -> Self { tree.into_leaves() }
+}
+
+impl TapTree {
+    /// Constructs tree consisting of a single leaf script.
+    pub fn with_single_leaf(script: impl Into<LeafScript>) -> Self {
+        TapTree(NonEmptyVec::with(LeafInfo::with(0, script.into())))
+    }
+
+    /// Constructs tree from leaves listed in depth-first order, validating
+    /// that their depths form a complete binary tree.
+    pub fn from_leaves(leaves: impl IntoIterator<Item = LeafInfo>) -> Result<Self, InvalidTree> {
+        let leaves = leaves.into_iter().collect::<Vec<_>>();
+        let mut stack = Vec::<u8>::with_capacity(TAPROOT_MAX_DEPTH as usize + 1);
+        for (no, leaf) in leaves.iter().enumerate() {
+            if leaf.depth > TAPROOT_MAX_DEPTH {
+                return Err(InvalidTree::TooDeep(no, leaf.depth));
+            }
+            if stack.first() == Some(&0) {
+                return Err(InvalidTree::Overfull(no));
+            }
+            let mut depth = leaf.depth;
+            while stack.last() == Some(&depth) && depth > 0 {
+                stack.pop();
+                depth -= 1;
+            }
+            stack.push(depth);
+        }
+        let count = leaves.len();
+        match stack.as_slice() {
+            [] => Err(InvalidTree::Empty),
+            [0] => NonEmptyVec::try_from(leaves)
+                .map(TapTree)
+                .map_err(|_| InvalidTree::TooManyLeaves(count)),
+            _ => Err(InvalidTree::Incomplete),
+        }
+    }
+
+    /// Returns tree leaves in depth-first order.
+    pub fn leaves(&self) -> &[LeafInfo] { self.0.as_slice() }
+
+    /// Releases the tree leaves in depth-first order.
+    pub fn into_leaves(self) -> Vec<LeafInfo> { self.0.into_inner() }
+
+    /// Computes merkle root of the tree.
+    pub fn merkle_root(&self) -> TapNodeHash { self.merklize(None).0 }
+
+    /// Computes merkle path for the leaf with a given depth-first index, for
+    /// use in [`ControlBlock`]. Returns `None` if the index is out of range.
+    pub fn merkle_path(&self, index: usize) -> Option<TapMerklePath> {
+        if index >= self.0.len() {
+            return None;
+        }
+        let path = self.merklize(Some(index)).1;
+        Some(TapMerklePath::try_from(path).expect("tree depth is validated on construction"))
+    }
+
+    /// Computes merkle root and, if a leaf index is given, merkle path for
+    /// that leaf.
+    fn merklize(&self, index: Option<usize>) -> (TapNodeHash, Vec<TapBranchHash>) {
+        let mut path = Vec::<TapBranchHash>::new();
+        // Stack of subtrees: depth of the subtree root, its hash, and the first
+        // leaf it contains
+        let mut stack = Vec::<(u8, TapNodeHash, usize)>::with_capacity(self.0.len());
+        for (no, leaf) in self.0.iter().enumerate() {
+            let mut node = (leaf.depth, TapNodeHash::from(leaf.script.tap_leaf_hash()), no);
+            while let Some(&(depth, hash, start)) = stack.last() {
+                if depth != node.0 || depth == 0 {
+                    break;
+                }
+                stack.pop();
+                let (_, sibling, mid) = node;
+                match index {
+                    Some(index) if (start..mid).contains(&index) => {
+                        path.push(TapBranchHash::from(sibling.into_inner()))
+                    }
+                    Some(index) if (mid..=no).contains(&index) => {
+                        path.push(TapBranchHash::from(hash.into_inner()))
+                    }
+                    _ => {}
+                }
+                node = (depth - 1, TapBranchHash::with_nodes(hash, sibling).into(), start);
+            }
+            stack.push(node);
+        }
+        let (_, root, _) = stack.pop().expect("tree is validated to be non-empty");
+        (root, path)
+    }
+}
+
+impl IntoTapHash for &TapTree {
+    fn into_tap_hash(self) -> TapNodeHash { self.merkle_root() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn leaf(depth: u8, op: u8) -> LeafInfo {
+        LeafInfo::tap_script(depth, TapScript::from_unsafe(vec![op]))
+    }
+
+    fn leaf_hash(op: u8) -> TapNodeHash {
+        TapScript::from_unsafe(vec![op]).tap_leaf_hash().into()
+    }
+
+    fn fold_path(leaf: TapNodeHash, path: &TapMerklePath) -> TapNodeHash {
+        path.iter().fold(leaf, |node, sibling| {
+            TapBranchHash::with_nodes(node, TapNodeHash::from(sibling.into_inner())).into()
+        })
+    }
+
+    #[test]
+    fn single_leaf() {
+        // BIP-341 wallet test vector
+        let script = LeafScript::with_bytes(
+            LeafVer::TapScript,
+            Vec::from_hex("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac")
+                .unwrap(),
+        )
+        .unwrap();
+        let tree = TapTree::with_single_leaf(script);
+        assert_eq!(
+            tree.merkle_root().to_string(),
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+        );
+        assert!(tree.merkle_path(0).unwrap().is_empty());
+        assert_eq!(tree.merkle_path(1), None);
+    }
+
+    #[test]
+    fn unbalanced_tree() {
+        // Tree ((A, (B, C)), D)
+        let leaves = [leaf(2, 0x51), leaf(3, 0x52), leaf(3, 0x53), leaf(1, 0x54)];
+        let tree = TapTree::from_leaves(leaves).unwrap();
+
+        let [a, b, c, d] = [0x51, 0x52, 0x53, 0x54].map(leaf_hash);
+        let bc = TapBranchHash::with_nodes(b, c).into();
+        let abc = TapBranchHash::with_nodes(a, bc).into();
+        let root = TapBranchHash::with_nodes(abc, d).into();
+        assert_eq!(tree.merkle_root(), root);
+
+        for (no, leaf) in [a, b, c, d].into_iter().enumerate() {
+            let path = tree.merkle_path(no).unwrap();
+            assert_eq!(path.len(), tree.leaves()[no].depth as usize);
+            assert_eq!(fold_path(leaf, &path), root);
+        }
+        assert_eq!(tree.merkle_path(0).unwrap()[0], TapBranchHash::from(bc.into_inner()));
+    }
+
+    #[test]
+    fn invalid_trees() {
+        assert_eq!(TapTree::from_leaves([]), Err(InvalidTree::Empty));
+        assert_eq!(TapTree::from_leaves([leaf(1, 0x51)]), Err(InvalidTree::Incomplete));
+        assert_eq!(
+            TapTree::from_leaves([leaf(1, 0x51), leaf(2, 0x52)]),
+            Err(InvalidTree::Incomplete)
+        );
+        assert_eq!(
+            TapTree::from_leaves([leaf(1, 0x51), leaf(1, 0x52), leaf(1, 0x53)]),
+            Err(InvalidTree::Overfull(2))
+        );
+        assert_eq!(TapTree::from_leaves([leaf(129, 0x51)]), Err(InvalidTree::TooDeep(0, 129)));
+    }
+
+    #[test]
+    fn strict_decoding() {
+        let tree = TapTree::from_leaves([leaf(1, 0x51), leaf(2, 0x52), leaf(2, 0x53)]).unwrap();
+        let data = tree.to_strict_serialized::<U32>().unwrap();
+        assert_eq!(TapTree::from_strict_serialized::<U32>(data).unwrap(), tree);
+
+        let incomplete = TapTree(NonEmptyVec::with(leaf(1, 0x51)));
+        let data = incomplete.to_strict_serialized::<U32>().unwrap();
+        assert!(TapTree::from_strict_serialized::<U32>(data).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_validation() {
+        use serde_crate::de::value::{Error, SeqDeserializer};
+        use serde_crate::Deserialize;
+
+        let empty = SeqDeserializer::<_, Error>::new(std::iter::empty::<u8>());
+        assert!(TapTree::deserialize(empty).is_err());
+        assert_eq!(TapTree::try_from(vec![leaf(1, 0x51)]), Err(InvalidTree::Incomplete));
+    }
+}
@@ -459,10 +459,10 @@ impl FromStr for Tx {
 
 impl Tx {
     #[inline]
-    pub fn inputs(&self) -> slice::Iter<TxIn> { self.inputs.iter() }
+    pub fn inputs(&self) -> slice::Iter<'_, TxIn> { self.inputs.iter() }
 
     #[inline]
-    pub fn outputs(&self) -> slice::Iter<TxOut> { self.outputs.iter() }
+    pub fn outputs(&self) -> slice::Iter<'_, TxOut> { self.outputs.iter() }
 
     #[inline]
     pub fn is_segwit(&self) -> bool { self.inputs().any(|txin| !txin.witness.is_empty()) }
@@ -0,0 +1,135 @@
+// Deterministic bitcoin commitments library.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use amplify::Bytes32;
+use bc::CompressedPk;
+use secp256k1::{Scalar, Secp256k1, SecretKey, Verification};
+
+use super::KeytweakError;
+use crate::LIB_NAME_BPCORE;
+
+/// Tweaking factor of the LNPBP-1 keytweak commitment.
+///
+/// The factor is added to the original public key during the commitment; the
+/// owner of the key must add the same factor to the private key in order to
+/// be able to sign for the tweaked public key.
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
+#[wrapper(Index, RangeOps, AsSlice, BorrowSlice, Hex, Display, FromStr)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_BPCORE)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", transparent)
+)]
+pub struct TweakingFactor(
+    #[from]
+    #[from([u8; 32])]
+    Bytes32,
+);
+
+impl TweakingFactor {
+    fn to_scalar(self) -> Result<Scalar, KeytweakError> {
+        Scalar::from_be_bytes(self.0.to_byte_array()).map_err(|_| KeytweakError::InvalidTweak(self))
+    }
+
+    /// Adds tweaking factor to a public key, producing the public key which
+    /// contains the commitment.
+    ///
+    /// Uses global secp256k1 context; see [`Self::apply_to_pubkey_with`] for
+    /// the version taking a caller-provided context.
+    pub fn apply_to_pubkey(self, pubkey: CompressedPk) -> Result<CompressedPk, KeytweakError> {
+        self.apply_to_pubkey_with(secp256k1::SECP256K1, pubkey)
+    }
+
+    /// Adds tweaking factor to a public key using the provided secp256k1
+    /// context, which may be a verification-only one.
+    pub fn apply_to_pubkey_with<C: Verification>(
+        self,
+        secp: &Secp256k1<C>,
+        pubkey: CompressedPk,
+    ) -> Result<CompressedPk, KeytweakError> {
+        pubkey
+            .add_exp_tweak(secp, &self.to_scalar()?)
+            .map(CompressedPk::from)
+            .map_err(|_| KeytweakError::InvalidTweak(self))
+    }
+
+    /// Adds tweaking factor to a secret key, producing the secret key which
+    /// corresponds to the public key containing the commitment.
+    ///
+    /// Secret key tweaking doesn't require a secp256k1 context.
+    pub fn apply_to_seckey(self, seckey: SecretKey) -> Result<SecretKey, KeytweakError> {
+        seckey
+            .add_tweak(&self.to_scalar()?)
+            .map_err(|_| KeytweakError::InvalidTweak(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use commit_verify::EmbedCommitVerify;
+    use commit_verify::mpc::Commitment;
+    use secp256k1::PublicKey;
+
+    use super::*;
+    use crate::keytweak::test::test_keys;
+
+    #[test]
+    fn spend_tweaked() {
+        let seckey = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let original_pk =
+            CompressedPk::from(PublicKey::from_secret_key(secp256k1::SECP256K1, &seckey));
+
+        let mut pk = original_pk;
+        let proof = pk.embed_commit(&Commitment::from([8u8; 32])).unwrap();
+
+        let tweaked_sk = proof.tweaking_factor.apply_to_seckey(seckey).unwrap();
+        let tweaked_pk = PublicKey::from_secret_key(secp256k1::SECP256K1, &tweaked_sk);
+        assert_eq!(CompressedPk::from(tweaked_pk), pk);
+        assert_eq!(proof.tweaking_factor.apply_to_pubkey(original_pk).unwrap(), pk);
+    }
+
+    #[test]
+    fn verification_ctx() {
+        let secp = Secp256k1::verification_only();
+        let factor = TweakingFactor::from([0xA5; 32]);
+        let [pk] = test_keys();
+        assert_eq!(factor.apply_to_pubkey_with(&secp, pk), factor.apply_to_pubkey(pk));
+    }
+
+    #[test]
+    fn overflow() {
+        let factor = TweakingFactor::from([0xFF; 32]);
+        let seckey = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        assert_eq!(factor.apply_to_seckey(seckey), Err(KeytweakError::InvalidTweak(factor)));
+    }
+
+    #[test]
+    fn display() {
+        let factor = TweakingFactor::from([0xA5; 32]);
+        let s = factor.to_string();
+        assert_eq!(s, "a5".repeat(32));
+        assert_eq!(TweakingFactor::from_str(&s).unwrap(), factor);
+    }
+}
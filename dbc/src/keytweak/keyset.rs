@@ -0,0 +1,153 @@
+// Deterministic bitcoin commitments library.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use bc::CompressedPk;
+use commit_verify::mpc::Commitment;
+use commit_verify::{EmbedCommitProof, EmbedCommitVerify, EmbedVerifyError};
+use secp256k1::PublicKey;
+
+use super::{tweak_key, Keytweak, KeytweakError, KeytweakProof};
+
+/// Set of public keys, one of which (the target key) receives LNPBP-1
+/// commitment tweak.
+///
+/// The tweaking factor commits to the sum of all keys in the set, such that
+/// the commitment can't be moved into some other key of the same set.
+#[derive(Getters, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Keyset {
+    /// Public key which absorbs the commitment tweak.
+    #[getter(as_copy)]
+    target: CompressedPk,
+
+    /// Other public keys participating in the commitment, excluding the
+    /// target key.
+    keys: BTreeSet<CompressedPk>,
+}
+
+impl Keyset {
+    /// Constructs keyset from a target key and a set of other keys. If the
+    /// target key is also present among the other keys it is not counted
+    /// twice.
+    pub fn with(target: CompressedPk, keys: impl IntoIterator<Item = CompressedPk>) -> Self {
+        let mut keys = keys.into_iter().collect::<BTreeSet<_>>();
+        keys.remove(&target);
+        Keyset { target, keys }
+    }
+
+    /// Computes sum of all public keys in the set, including the target key.
+    pub fn sum(&self) -> Result<PublicKey, KeytweakError> {
+        let keys = self.iter().map(|pk| *pk).collect::<Vec<_>>();
+        let refs = keys.iter().collect::<Vec<_>>();
+        PublicKey::combine_keys(&refs).map_err(|_| KeytweakError::SumInfinity)
+    }
+
+    /// Iterates over all keys in the set, starting with the target key.
+    pub fn iter(&self) -> impl Iterator<Item = CompressedPk> + '_ {
+        std::iter::once(self.target).chain(self.keys.iter().copied())
+    }
+}
+
+impl EmbedCommitProof<Commitment, Keyset, Keytweak> for KeytweakProof {
+    fn restore_original_container(
+        &self,
+        commit_container: &Keyset,
+    ) -> Result<Keyset, EmbedVerifyError<KeytweakError>> {
+        Ok(Keyset {
+            target: self.original_pk,
+            keys: commit_container.keys.clone(),
+        })
+    }
+}
+
+impl EmbedCommitVerify<Commitment, Keytweak> for Keyset {
+    type Proof = KeytweakProof;
+    type CommitError = KeytweakError;
+
+    fn embed_commit(&mut self, msg: &Commitment) -> Result<Self::Proof, Self::CommitError> {
+        let original_pk = self.target;
+        let (tweaked, tweaking_factor) = tweak_key(original_pk, self.sum()?, msg)?;
+        self.target = tweaked;
+        Ok(KeytweakProof {
+            original_pk,
+            tweaking_factor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keytweak::test::test_keys;
+
+    #[test]
+    fn roundtrip() {
+        let [target, a, b] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+        let mut keyset = Keyset::with(target, [a, b, target]);
+        assert_eq!(keyset.keys().len(), 2);
+
+        let proof = keyset.embed_commit(&msg).unwrap();
+        assert_eq!(proof.original_pk, target);
+        assert_ne!(keyset.target(), target);
+        assert_eq!(keyset.keys(), &bset![a, b]);
+
+        keyset.verify(&msg, &proof).unwrap();
+        assert!(keyset.verify(&Commitment::from([9u8; 32]), &proof).is_err());
+    }
+
+    #[test]
+    fn keyset_binding() {
+        let [target, a, b] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+
+        let mut single = target;
+        let mut keyset = Keyset::with(target, [a, b]);
+        let single_proof = single.embed_commit(&msg).unwrap();
+        let keyset_proof = keyset.embed_commit(&msg).unwrap();
+
+        // The same key in a different set must receive a different tweak
+        assert_ne!(single_proof.tweaking_factor, keyset_proof.tweaking_factor);
+        assert_ne!(single, keyset.target());
+
+        // The tweak must not depend on the order of the other keys
+        let mut reordered = Keyset::with(target, [b, a]);
+        assert_eq!(reordered.embed_commit(&msg).unwrap(), keyset_proof);
+    }
+
+    #[test]
+    fn known_answer() {
+        let [target, other, _] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+        let mut keyset = Keyset::with(target, [other]);
+        let proof = keyset.embed_commit(&msg).unwrap();
+
+        assert_eq!(
+            proof.tweaking_factor.to_string(),
+            "4c7281eb33f641c0e408c8e79f45de2e74a41e0b6db183136ba829e74705c228"
+        );
+        assert_eq!(
+            keyset.target().to_string(),
+            "03837ff90c0d0ea168705df650625f10eaaa9c67ddb8fa836ae73f6efb1691a6d3"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// Deterministic bitcoin commitments library.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use bc::opcodes::{OP_PUSHBYTES_75, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4};
+use bc::{CompressedPk, UncompressedPk, WitnessScript};
+use commit_verify::mpc::Commitment;
+use commit_verify::{EmbedCommitProof, EmbedCommitVerify, EmbedVerifyError};
+use secp256k1::PublicKey;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+use super::{tweak_key, Keytweak, KeytweakError, TweakingFactor};
+use crate::LIB_NAME_BPCORE;
+
+/// Policy selecting which of the public keys present in a lockscript receive
+/// the commitment tweak.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_BPCORE, tags = order)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub enum KeyPolicy {
+    /// All public keys in the script are tweaked with the same tweaking
+    /// factor.
+    #[display("all")]
+    #[strict_type(dumb)]
+    All,
+
+    /// Only the public key at a given position (counting from zero, in order
+    /// of appearance in the script) is tweaked.
+    #[display("#{0}")]
+    Index(u16),
+
+    /// Only the first occurrence of a given public key in the script is
+    /// tweaked.
+    #[display("{0}")]
+    FirstMatching(CompressedPk),
+}
+
+/// Lockscript (witness script of P2WSH or P2WSH-in-P2SH output) with a policy
+/// defining which of its public keys receive the commitment tweak.
+///
+/// The tweaking factor commits to the sum of all distinct public keys found
+/// in the script.
+#[derive(Getters, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Lockscript {
+    /// Lockscript containing public keys.
+    script: WitnessScript,
+
+    /// Policy selecting keys which are tweaked by the commitment.
+    #[getter(as_copy)]
+    policy: KeyPolicy,
+}
+
+impl Lockscript {
+    /// Constructs lockscript container from a witness script and a key policy.
+    pub fn with(script: WitnessScript, policy: KeyPolicy) -> Self { Lockscript { script, policy } }
+
+    /// Lists all compressed public keys present in the script, in the order of
+    /// their appearance, together with their byte offsets.
+    ///
+    /// Errors if the script contains an uncompressed public key: such keys
+    /// can't be tweaked in place, and ignoring them would leave them out of
+    /// the key sum the commitment is bound to.
+    pub fn keys(&self) -> Result<Vec<(usize, CompressedPk)>, KeytweakError> {
+        let script = self.script.as_slice();
+        let mut keys = vec![];
+        let mut pos = 0usize;
+        while pos < script.len() {
+            let op_code = script[pos];
+            pos += 1;
+            let len_bytes = match op_code {
+                OP_PUSHDATA1 => 1,
+                OP_PUSHDATA2 => 2,
+                OP_PUSHDATA4 => 4,
+                0x01..=OP_PUSHBYTES_75 => 0,
+                _ => continue,
+            };
+            let len = if len_bytes == 0 {
+                op_code as usize
+            } else {
+                let end = pos
+                    .checked_add(len_bytes)
+                    .ok_or(KeytweakError::TruncatedScript)?;
+                let data = script
+                    .get(pos..end)
+                    .ok_or(KeytweakError::TruncatedScript)?;
+                pos = end;
+                let mut buf = [0u8; 4];
+                buf[..len_bytes].copy_from_slice(data);
+                u32::from_le_bytes(buf) as usize
+            };
+            // Push length may come from untrusted `OP_PUSHDATA4` and overflow
+            // 32-bit `usize`
+            let end = pos.checked_add(len).ok_or(KeytweakError::TruncatedScript)?;
+            let data = script
+                .get(pos..end)
+                .ok_or(KeytweakError::TruncatedScript)?;
+            if let Ok(data) = <[u8; 33]>::try_from(data) {
+                if let Ok(pk) = CompressedPk::from_byte_array(data) {
+                    keys.push((pos, pk));
+                }
+            } else if let Ok(data) = <[u8; 65]>::try_from(data) {
+                if let Ok(pk) = UncompressedPk::from_byte_array(data) {
+                    return Err(KeytweakError::UncompressedKey(pk));
+                }
+            }
+            pos = end;
+        }
+        Ok(keys)
+    }
+}
+
+/// Proof of the commitment to a lockscript.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_BPCORE)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct LockscriptProof {
+    /// Lockscript before the commitment tweak was applied.
+    pub original_script: WitnessScript,
+
+    /// Policy used for selecting tweaked keys.
+    pub policy: KeyPolicy,
+
+    /// Tweaking factor which was added to the selected public keys.
+    pub tweaking_factor: TweakingFactor,
+}
+
+impl StrictSerialize for LockscriptProof {}
+impl StrictDeserialize for LockscriptProof {}
+
+impl EmbedCommitProof<Commitment, Lockscript, Keytweak> for LockscriptProof {
+    fn restore_original_container(
+        &self,
+        _: &Lockscript,
+    ) -> Result<Lockscript, EmbedVerifyError<KeytweakError>> {
+        Ok(Lockscript::with(self.original_script.clone(), self.policy))
+    }
+}
+
+impl EmbedCommitVerify<Commitment, Keytweak> for Lockscript {
+    type Proof = LockscriptProof;
+    type CommitError = KeytweakError;
+
+    fn embed_commit(&mut self, msg: &Commitment) -> Result<Self::Proof, Self::CommitError> {
+        let keys = self.keys()?;
+        let selected = match self.policy {
+            KeyPolicy::All => keys.clone(),
+            KeyPolicy::Index(index) => vec![*keys
+                .get(index as usize)
+                .ok_or(KeytweakError::KeyIndexOutOfRange(index, keys.len()))?],
+            KeyPolicy::FirstMatching(pk) => vec![*keys
+                .iter()
+                .find(|(_, key)| *key == pk)
+                .ok_or(KeytweakError::KeyNotFound(pk))?],
+        };
+        let Some(&(_, first)) = selected.first() else {
+            return Err(KeytweakError::NoKeys);
+        };
+
+        let keyset = keys
+            .iter()
+            .map(|(_, pk)| **pk)
+            .collect::<BTreeSet<PublicKey>>();
+        let refs = keyset.iter().collect::<Vec<_>>();
+        let sum = PublicKey::combine_keys(&refs).map_err(|_| KeytweakError::SumInfinity)?;
+        let (_, tweaking_factor) = tweak_key(first, sum, msg)?;
+
+        let original_script = self.script.clone();
+        let script: &mut [u8] = self.script.as_mut();
+        for (pos, pk) in selected {
+            let tweaked = tweaking_factor.apply_to_pubkey(pk)?;
+            script[pos..pos + 33].copy_from_slice(&tweaked.to_byte_array());
+        }
+
+        Ok(LockscriptProof {
+            original_script,
+            policy: self.policy,
+            tweaking_factor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bc::opcodes::{OP_CHECKMULTISIG, OP_PUSHBYTES_33, OP_PUSHBYTES_65, OP_PUSHNUM_2};
+
+    use super::*;
+    use crate::keytweak::test::test_keys;
+
+    fn multisig() -> WitnessScript {
+        let [a, b] = test_keys();
+        let mut script = vec![OP_PUSHNUM_2, OP_PUSHBYTES_33];
+        script.extend(a.to_byte_array());
+        script.push(OP_PUSHBYTES_33);
+        script.extend(b.to_byte_array());
+        script.extend([OP_PUSHNUM_2, OP_CHECKMULTISIG]);
+        WitnessScript::from_unsafe(script)
+    }
+
+    fn commit(
+        script: WitnessScript,
+        policy: KeyPolicy,
+    ) -> Result<(Lockscript, LockscriptProof), KeytweakError> {
+        let mut lockscript = Lockscript::with(script, policy);
+        let proof = lockscript.embed_commit(&Commitment::from([8u8; 32]))?;
+        Ok((lockscript, proof))
+    }
+
+    #[test]
+    fn key_extraction() {
+        let [a, b] = test_keys();
+        let lockscript = Lockscript::with(multisig(), KeyPolicy::All);
+        assert_eq!(lockscript.keys().unwrap(), vec![(2, a), (36, b)]);
+    }
+
+    #[test]
+    fn policies() {
+        let [a, b] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+
+        let (all, proof) = commit(multisig(), KeyPolicy::All).unwrap();
+        let tweaked = all.keys().unwrap();
+        assert_eq!(tweaked[0].1, proof.tweaking_factor.apply_to_pubkey(a).unwrap());
+        assert_eq!(tweaked[1].1, proof.tweaking_factor.apply_to_pubkey(b).unwrap());
+        all.verify(&msg, &proof).unwrap();
+
+        let (second, proof) = commit(multisig(), KeyPolicy::Index(1)).unwrap();
+        assert_eq!(second.keys().unwrap()[0].1, a);
+        assert_ne!(second.keys().unwrap()[1].1, b);
+        second.verify(&msg, &proof).unwrap();
+
+        let (first, proof) = commit(multisig(), KeyPolicy::FirstMatching(a)).unwrap();
+        assert_ne!(first.keys().unwrap()[0].1, a);
+        assert_eq!(first.keys().unwrap()[1].1, b);
+        first.verify(&msg, &proof).unwrap();
+        assert!(first.verify(&Commitment::from([9u8; 32]), &proof).is_err());
+    }
+
+    #[test]
+    fn policy_errors() {
+        let [a, _] = test_keys();
+        let uncompressed = UncompressedPk::from(*a);
+        assert_eq!(
+            commit(multisig(), KeyPolicy::Index(2)).unwrap_err(),
+            KeytweakError::KeyIndexOutOfRange(2, 2)
+        );
+        assert_eq!(
+            commit(WitnessScript::new(), KeyPolicy::FirstMatching(a)).unwrap_err(),
+            KeytweakError::KeyNotFound(a)
+        );
+        assert_eq!(
+            commit(WitnessScript::new(), KeyPolicy::All).unwrap_err(),
+            KeytweakError::NoKeys
+        );
+        let mut script = vec![OP_PUSHBYTES_65];
+        script.extend(uncompressed.to_byte_array());
+        assert_eq!(
+            commit(WitnessScript::from_unsafe(script), KeyPolicy::All).unwrap_err(),
+            KeytweakError::UncompressedKey(uncompressed)
+        );
+        assert_eq!(
+            commit(WitnessScript::from_unsafe(vec![OP_PUSHBYTES_33, 0x02]), KeyPolicy::All)
+                .unwrap_err(),
+            KeytweakError::TruncatedScript
+        );
+        assert_eq!(
+            commit(
+                WitnessScript::from_unsafe(vec![OP_PUSHDATA4, 0xFF, 0xFF, 0xFF, 0xFF]),
+                KeyPolicy::All
+            )
+            .unwrap_err(),
+            KeytweakError::TruncatedScript
+        );
+    }
+}
@@ -31,3 +31,143 @@
 //! d) `PubkeyScript, SpkDescriptor, Msg -> PubkeyScript'`;
 //! e) `TxOut, SpkDescriptor, Msg -> TxOut'`;
 //! f) `Tx, SpkDescriptor, Msg -> Tx'`;
+//!
+//! The tweaking factor follows LNPBP-1:
+//! `f = HMAC_SHA256(key = S, SHA256("LNPBP1") || SHA256(KEYTWEAK_TAG) || m)`,
+//! where `S` is the 33-byte compressed serialization of the sum of all public
+//! keys participating in the commitment and `m` is the 32-byte multi-protocol
+//! commitment. Only the target key is tweaked: `P' = P + f·G`.
+
+mod factor;
+mod pubkey;
+mod keyset;
+mod lockscript;
+mod multisig;
+
+use bc::{CompressedPk, UncompressedPk};
+use commit_verify::mpc::Commitment;
+use commit_verify::{CommitmentProtocol, Digest, Sha256};
+use hmac::{Hmac, Mac};
+use secp256k1::PublicKey;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+
+pub use self::factor::TweakingFactor;
+pub use self::keyset::Keyset;
+pub use self::lockscript::{KeyPolicy, Lockscript, LockscriptProof};
+pub use self::multisig::{SortedMulti, MAX_MULTISIG_KEYS};
+use crate::LIB_NAME_BPCORE;
+
+/// LNPBP-1 tag, which hash prefixes all LNPBP-1 commitment messages.
+pub const LNPBP1_TAG: &str = "LNPBP1";
+
+/// Protocol-specific tag used in computing LNPBP-1 tweaking factor for the
+/// keytweak deterministic bitcoin commitments.
+pub const KEYTWEAK_TAG: &str = "urn:lnp-bp:dbc:keytweak#2024-02-03";
+
+/// Marker non-instantiable enum defining public key tweaking (`keytweak`)
+/// protocol.
+pub enum Keytweak {}
+
+impl CommitmentProtocol for Keytweak {}
+
+/// Errors during keytweak commitment.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+#[display(doc_comments)]
+pub enum KeytweakError {
+    /// sum of the public keys in the keyset results in a point at infinity.
+    SumInfinity,
+
+    /// tweaking factor {0} exceeds secp256k1 curve order or its application
+    /// results in a point at infinity.
+    InvalidTweak(TweakingFactor),
+
+    /// lockscript doesn't contain any compressed public keys.
+    NoKeys,
+
+    /// key index {0} is out of range for a lockscript containing {1} public
+    /// keys.
+    KeyIndexOutOfRange(u16, usize),
+
+    /// lockscript doesn't contain public key {0}.
+    KeyNotFound(CompressedPk),
+
+    /// lockscript is truncated inside a data push.
+    TruncatedScript,
+
+    /// lockscript contains uncompressed public key {0}, which can't be used
+    /// in keytweak commitments.
+    UncompressedKey(UncompressedPk),
+
+    /// threshold {0} is invalid for a multisig with {1} public keys.
+    InvalidThreshold(u8, usize),
+
+    /// multisig with {0} public keys exceeds the `OP_CHECKMULTISIG` limit of
+    /// 20 keys.
+    TooManyKeys(usize),
+}
+
+/// Proof of the keytweak commitment, containing original (untweaked) value of
+/// the public key which received the tweak.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_BPCORE)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", rename_all = "camelCase")
+)]
+pub struct KeytweakProof {
+    /// The public key before the commitment tweak was applied.
+    pub original_pk: CompressedPk,
+
+    /// Tweaking factor which was added to the original public key.
+    ///
+    /// Wallets must apply the same factor to the private key corresponding to
+    /// [`Self::original_pk`] (see [`TweakingFactor::apply_to_seckey`]) in
+    /// order to spend the output.
+    pub tweaking_factor: TweakingFactor,
+}
+
+impl StrictSerialize for KeytweakProof {}
+impl StrictDeserialize for KeytweakProof {}
+
+/// Tweaks `target` public key with a commitment to `msg` according to LNPBP-1
+/// rules, using `sum` of all public keys participating in the commitment.
+///
+/// Returns tweaked key and the used tweaking factor.
+fn tweak_key(
+    target: CompressedPk,
+    sum: PublicKey,
+    msg: &Commitment,
+) -> Result<(CompressedPk, TweakingFactor), KeytweakError> {
+    let mut engine =
+        Hmac::<Sha256>::new_from_slice(&sum.serialize()).expect("HMAC accepts keys of any size");
+    engine.update(&Sha256::digest(LNPBP1_TAG));
+    engine.update(&Sha256::digest(KEYTWEAK_TAG));
+    engine.update(msg.as_slice());
+    let factor = TweakingFactor::from(<[u8; 32]>::from(engine.finalize().into_bytes()));
+    let tweaked = factor.apply_to_pubkey(target)?;
+    Ok((tweaked, factor))
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    /// Test public keys `G`, `2G` and `3G`, shared by keytweak test modules.
+    pub(super) fn test_keys<const N: usize>() -> [CompressedPk; N] {
+        const KEYS: [&str; 3] = [
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        ];
+        core::array::from_fn(|i| CompressedPk::from_str(KEYS[i]).unwrap())
+    }
+}
@@ -0,0 +1,200 @@
+// Deterministic bitcoin commitments library.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bc::opcodes::{OP_CHECKMULTISIG, OP_PUSHBYTES_1, OP_PUSHBYTES_33, OP_PUSHNUM_1};
+use bc::{CompressedPk, WitnessScript};
+use commit_verify::mpc::Commitment;
+use commit_verify::{EmbedCommitProof, EmbedCommitVerify, EmbedVerifyError};
+
+use super::{Keyset, Keytweak, KeytweakError, KeytweakProof};
+
+/// Maximum number of public keys allowed in `OP_CHECKMULTISIG`.
+pub const MAX_MULTISIG_KEYS: usize = 20;
+
+/// Threshold k-of-n multisig with lexicographically sorted keys
+/// (`sortedmulti`), one of which receives the commitment tweak according to
+/// the keyset rules.
+///
+/// The witness script is regenerated from the keys and the threshold, so
+/// proofs do not need to carry the original script.
+#[derive(Getters, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SortedMulti {
+    /// Number of signatures required to spend.
+    #[getter(as_copy)]
+    threshold: u8,
+
+    /// Keys of the multisig, with the target key receiving the tweak.
+    keyset: Keyset,
+}
+
+impl SortedMulti {
+    /// Constructs k-of-n multisig container, checking that the number of keys
+    /// is not above [`MAX_MULTISIG_KEYS`] and that the threshold is non-zero
+    /// and doesn't exceed the number of keys.
+    pub fn with(threshold: u8, keyset: Keyset) -> Result<Self, KeytweakError> {
+        let count = keyset.keys().len() + 1;
+        if count > MAX_MULTISIG_KEYS {
+            return Err(KeytweakError::TooManyKeys(count));
+        }
+        if threshold == 0 || threshold as usize > count {
+            return Err(KeytweakError::InvalidThreshold(threshold, count));
+        }
+        Ok(SortedMulti { threshold, keyset })
+    }
+
+    /// Iterates over all multisig keys in the order they appear in the
+    /// witness script.
+    pub fn sorted_keys(&self) -> impl Iterator<Item = CompressedPk> {
+        let mut keys = self.keyset.iter().collect::<Vec<_>>();
+        keys.sort_by_key(CompressedPk::to_byte_array);
+        keys.into_iter()
+    }
+
+    /// Generates `OP_CHECKMULTISIG` witness script.
+    pub fn to_witness_script(&self) -> WitnessScript {
+        let keys = self.sorted_keys().collect::<Vec<_>>();
+        let mut script = Vec::with_capacity(keys.len() * 34 + 5);
+        push_num(&mut script, self.threshold);
+        for pk in &keys {
+            script.push(OP_PUSHBYTES_33);
+            script.extend(pk.to_byte_array());
+        }
+        push_num(&mut script, keys.len() as u8);
+        script.push(OP_CHECKMULTISIG);
+        WitnessScript::from_unsafe(script)
+    }
+}
+
+fn push_num(script: &mut Vec<u8>, num: u8) {
+    match num {
+        1..=16 => script.push(OP_PUSHNUM_1 + num - 1),
+        _ => script.extend([OP_PUSHBYTES_1, num]),
+    }
+}
+
+impl EmbedCommitProof<Commitment, SortedMulti, Keytweak> for KeytweakProof {
+    fn restore_original_container(
+        &self,
+        commit_container: &SortedMulti,
+    ) -> Result<SortedMulti, EmbedVerifyError<KeytweakError>> {
+        let keyset = Keyset::with(self.original_pk, commit_container.keyset.keys().iter().copied());
+        Ok(SortedMulti {
+            threshold: commit_container.threshold,
+            keyset,
+        })
+    }
+}
+
+impl EmbedCommitVerify<Commitment, Keytweak> for SortedMulti {
+    type Proof = KeytweakProof;
+    type CommitError = KeytweakError;
+
+    fn embed_commit(&mut self, msg: &Commitment) -> Result<Self::Proof, Self::CommitError> {
+        self.keyset.embed_commit(msg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bc::opcodes::OP_PUSHNUM_16;
+    use secp256k1::{PublicKey, SecretKey, SECP256K1};
+
+    use super::*;
+    use crate::keytweak::test::test_keys;
+
+    fn keyset(count: u8) -> Keyset {
+        let mut keys = (1..=count).map(|i| {
+            let sk = SecretKey::from_slice(&[i; 32]).unwrap();
+            CompressedPk::from(PublicKey::from_secret_key(SECP256K1, &sk))
+        });
+        let target = keys.next().unwrap();
+        Keyset::with(target, keys)
+    }
+
+    #[test]
+    fn script() {
+        let [a, b, target] = test_keys();
+        let multisig = SortedMulti::with(2, Keyset::with(target, [a, b])).unwrap();
+        let script = multisig.to_witness_script();
+        let mut expected = vec![0x52];
+        for pk in [a, b, target] {
+            expected.push(0x21);
+            expected.extend(pk.to_byte_array());
+        }
+        expected.extend([0x53, 0xae]);
+        assert_eq!(script.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let [a, b, target] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+        let keyset = Keyset::with(target, [a, b]);
+        let mut multisig = SortedMulti::with(2, keyset.clone()).unwrap();
+        let original_script = multisig.to_witness_script();
+
+        let proof = multisig.embed_commit(&msg).unwrap();
+        assert_eq!(proof, keyset.clone().embed_commit(&msg).unwrap());
+        assert_ne!(multisig.to_witness_script(), original_script);
+        assert_eq!(multisig.threshold(), 2);
+
+        multisig.verify(&msg, &proof).unwrap();
+        assert!(multisig.verify(&Commitment::from([9u8; 32]), &proof).is_err());
+    }
+
+    #[test]
+    fn threshold() {
+        let [a, b, target] = test_keys();
+        let keyset = Keyset::with(target, [a, b]);
+        assert_eq!(
+            SortedMulti::with(0, keyset.clone()).unwrap_err(),
+            KeytweakError::InvalidThreshold(0, 3)
+        );
+        assert_eq!(
+            SortedMulti::with(4, keyset.clone()).unwrap_err(),
+            KeytweakError::InvalidThreshold(4, 3)
+        );
+        assert!(SortedMulti::with(3, keyset).is_ok());
+    }
+
+    #[test]
+    fn too_many_keys() {
+        assert!(SortedMulti::with(1, keyset(20)).is_ok());
+        assert_eq!(
+            SortedMulti::with(1, keyset(21)).unwrap_err(),
+            KeytweakError::TooManyKeys(21)
+        );
+    }
+
+    #[test]
+    fn large_multisig_script() {
+        let script = SortedMulti::with(16, keyset(16)).unwrap().to_witness_script();
+        assert_eq!(script[0], OP_PUSHNUM_16);
+        assert_eq!(script[script.len() - 2..], [OP_PUSHNUM_16, OP_CHECKMULTISIG]);
+
+        for count in 17..=20 {
+            let script = SortedMulti::with(17, keyset(count)).unwrap().to_witness_script();
+            assert_eq!(script.len(), 2 + count as usize * 34 + 3);
+            assert_eq!(script[..2], [OP_PUSHBYTES_1, 17]);
+            assert_eq!(script[script.len() - 3..], [OP_PUSHBYTES_1, count, OP_CHECKMULTISIG]);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// Deterministic bitcoin commitments library.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2019-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2019-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bc::CompressedPk;
+use commit_verify::mpc::Commitment;
+use commit_verify::{EmbedCommitProof, EmbedCommitVerify, EmbedVerifyError};
+
+use super::{tweak_key, Keytweak, KeytweakError, KeytweakProof};
+
+impl EmbedCommitProof<Commitment, CompressedPk, Keytweak> for KeytweakProof {
+    fn restore_original_container(
+        &self,
+        _: &CompressedPk,
+    ) -> Result<CompressedPk, EmbedVerifyError<KeytweakError>> {
+        Ok(self.original_pk)
+    }
+}
+
+impl EmbedCommitVerify<Commitment, Keytweak> for CompressedPk {
+    type Proof = KeytweakProof;
+    type CommitError = KeytweakError;
+
+    fn embed_commit(&mut self, msg: &Commitment) -> Result<Self::Proof, Self::CommitError> {
+        let original_pk = *self;
+        let (tweaked, tweaking_factor) = tweak_key(original_pk, *original_pk, msg)?;
+        *self = tweaked;
+        Ok(KeytweakProof {
+            original_pk,
+            tweaking_factor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use commit_verify::EmbedVerifyError;
+
+    use super::*;
+    use crate::keytweak::test::test_keys;
+
+    #[test]
+    fn roundtrip() {
+        let [original_pk] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+        let mut pk = original_pk;
+        let proof = pk.embed_commit(&msg).unwrap();
+
+        assert_ne!(pk, original_pk);
+        assert_eq!(proof.original_pk, original_pk);
+
+        let mut pk_prime = original_pk;
+        assert_eq!(pk_prime.embed_commit(&msg).unwrap(), proof);
+        assert_eq!(pk_prime, pk);
+
+        pk.verify(&msg, &proof).unwrap();
+    }
+
+    #[test]
+    fn wrong_message() {
+        let [original_pk] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+        let mut pk = original_pk;
+        let proof = pk.embed_commit(&msg).unwrap();
+
+        assert_eq!(
+            pk.verify(&Commitment::from([9u8; 32]), &proof),
+            Err(EmbedVerifyError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn known_answer() {
+        let [original_pk] = test_keys();
+        let msg = Commitment::from([8u8; 32]);
+        let mut pk = original_pk;
+        let proof = pk.embed_commit(&msg).unwrap();
+
+        assert_eq!(
+            proof.tweaking_factor.to_string(),
+            "16837a1684469d81d89005fac804269d5b935963cf70984102c6f6531c396903"
+        );
+        assert_eq!(
+            pk.to_string(),
+            "0324361b6ca1179c594d7862ba22debd807d81833412b8cce6c1cf3624bc942b40"
+        );
+    }
+}
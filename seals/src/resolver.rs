@@ -21,6 +21,8 @@
 
 //! API for resolving single-use-seals.
 
+use std::collections::{BTreeMap, HashMap};
+
 use bc::{Tx, Txid};
 
 /// Error resolving single-use-seal
@@ -40,3 +42,48 @@ pub trait Resolver {
     /// Return transaction data for a given transaction id.
     fn tx_by_id(&self, txid: Txid) -> Result<Tx, Error>;
 }
+
+impl Resolver for BTreeMap<Txid, Tx> {
+    fn tx_by_id(&self, txid: Txid) -> Result<Tx, Error> {
+        self.get(&txid).cloned().ok_or(Error::UnknownTx(txid))
+    }
+}
+
+impl Resolver for HashMap<Txid, Tx> {
+    fn tx_by_id(&self, txid: Txid) -> Result<Tx, Error> {
+        self.get(&txid).cloned().ok_or(Error::UnknownTx(txid))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bc::{LockTime, TxVer, VarIntArray};
+
+    use super::*;
+
+    fn tx(lock_time: u32) -> Tx {
+        Tx {
+            version: TxVer::default(),
+            inputs: VarIntArray::new(),
+            outputs: VarIntArray::new(),
+            lock_time: LockTime::from_consensus_u32(lock_time),
+        }
+    }
+
+    fn check(resolver: &impl Resolver) {
+        let known = tx(0);
+        assert_eq!(resolver.tx_by_id(known.txid()).unwrap(), known);
+
+        let unknown = tx(1).txid();
+        assert!(matches!(
+            resolver.tx_by_id(unknown),
+            Err(Error::UnknownTx(txid)) if txid == unknown
+        ));
+    }
+
+    #[test]
+    fn btree_map() { check(&BTreeMap::from([(tx(0).txid(), tx(0))])); }
+
+    #[test]
+    fn hash_map() { check(&HashMap::from([(tx(0).txid(), tx(0))])); }
+}
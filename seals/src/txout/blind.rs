@@ -27,7 +27,7 @@ use std::str::FromStr;
 
 use amplify::hex;
 use bc::{Outpoint, Txid, Vout};
-use commit_verify::{CommitId, Conceal};
+use commit_verify::{CommitId, Conceal, DigestExt, Sha256};
 use dbc::MethodParseError;
 use rand::{thread_rng, RngCore};
 use strict_encoding::{StrictDecode, StrictDumb, StrictEncode};
@@ -36,6 +36,67 @@ use super::{CloseMethod, WitnessVoutError};
 use crate::txout::{SealTxid, TxPtr, TxoSeal};
 use crate::{SealCloseMethod, SecretSeal};
 
+/// Tag used by [`BlindingScheme::TaggedSha256`] derivation of seal blinding
+/// factors. Uses the same version date as the secret seal tag and must not
+/// change: a new derivation requires a new [`BlindingScheme`] variant.
+pub const BLINDING_DERIVATION_TAG: &str = "urn:lnp-bp:seals:blinding#2024-02-03";
+
+/// Scheme used to deterministically derive seal blinding factors.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum BlindingScheme {
+    /// The blinding is the first 8 bytes (little-endian) of a SHA256 hash
+    /// tagged with [`BLINDING_DERIVATION_TAG`] over the seed followed by
+    /// little-endian account and index values.
+    #[default]
+    TaggedSha256,
+}
+
+/// Seal blinding factor deterministically derived from a wallet seed, account
+/// and index, allowing wallets to recover blinding factors of their seals from
+/// a backup instead of persisting each random blinding.
+///
+/// The factor keeps the identifier of the scheme used in its derivation, so
+/// blindings produced by different schemes remain distinguishable.
+#[derive(Getters, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BlindingFactor {
+    /// Scheme used to derive the blinding factor.
+    #[getter(as_copy)]
+    scheme: BlindingScheme,
+
+    /// Blinding value used in seal definitions.
+    #[getter(as_copy)]
+    value: u64,
+}
+
+impl BlindingFactor {
+    /// Derives blinding factor using the default [`BlindingScheme`].
+    pub fn derive(seed: &[u8; 32], account: u32, index: u32) -> Self {
+        Self::derive_with(BlindingScheme::default(), seed, account, index)
+    }
+
+    /// Derives blinding factor using a specific [`BlindingScheme`].
+    pub fn derive_with(scheme: BlindingScheme, seed: &[u8; 32], account: u32, index: u32) -> Self {
+        let value = match scheme {
+            BlindingScheme::TaggedSha256 => {
+                let mut engine = Sha256::from_tag(BLINDING_DERIVATION_TAG);
+                engine.input_raw(seed);
+                engine.input_raw(&account.to_le_bytes());
+                engine.input_raw(&index.to_le_bytes());
+                let hash = engine.finish();
+                let mut blinding = [0u8; 8];
+                blinding.copy_from_slice(&hash[..8]);
+                u64::from_le_bytes(blinding)
+            }
+        };
+        BlindingFactor { scheme, value }
+    }
+}
+
+impl From<BlindingFactor> for u64 {
+    fn from(factor: BlindingFactor) -> Self { factor.value }
+}
+
 /// Seal type which can be blinded and chained with other seals.
 pub type ChainBlindSeal<M> = BlindSeal<TxPtr, M>;
 /// Seal type which can be blinded, but can't be chained with other seals.
@@ -199,6 +260,21 @@ impl<Id: SealTxid, M: SealCloseMethod> BlindSeal<Id, M> {
             blinding,
         }
     }
+
+    /// Creates new seal for the provided outpoint and seal closing method,
+    /// deriving the blinding factor from a wallet seed with
+    /// [`BlindingFactor::derive`].
+    pub fn with_derived_blinding(
+        method: M,
+        txid: impl Into<Id>,
+        vout: impl Into<Vout>,
+        seed: &[u8; 32],
+        account: u32,
+        index: u32,
+    ) -> Self {
+        let blinding = BlindingFactor::derive(seed, account, index);
+        BlindSeal::with_blinding(method, txid, vout, blinding.value())
+    }
 }
 
 impl<M: SealCloseMethod> BlindSeal<TxPtr, M> {
@@ -478,4 +554,31 @@ mod test {
             Err(ParseError::MethodRequired)
         );
     }
+
+    #[test]
+    fn derived_blinding() {
+        let seed = [0xA5; 32];
+        let txid =
+            Txid::from_str("646ca5c1062619e2a2d60771c9dfd820551fb773e4dc8c4ed67965a8d1fae839")
+                .unwrap();
+        let seal = SingleBlindSeal::with_derived_blinding(
+            CloseMethod::TapretFirst,
+            txid,
+            0u32,
+            &seed,
+            0,
+            1,
+        );
+        let factor = BlindingFactor::derive(&seed, 0, 1);
+        assert_eq!(factor.scheme(), BlindingScheme::TaggedSha256);
+        assert_eq!(factor.value(), 0x6412a0976c46edd6);
+        assert_eq!(seal.blinding, factor.value());
+        assert_eq!(
+            seal,
+            SingleBlindSeal::with_blinding(CloseMethod::TapretFirst, txid, 0u32, seal.blinding)
+        );
+        assert_ne!(factor, BlindingFactor::derive(&seed, 0, 2));
+        assert_ne!(factor, BlindingFactor::derive(&seed, 1, 1));
+        assert_ne!(factor, BlindingFactor::derive(&[0x5A; 32], 0, 1));
+    }
 }
@@ -21,7 +21,7 @@
 
 use std::error::Error;
 
-use bc::Outpoint;
+use bc::{Outpoint, Txid};
 
 /// Seal verification errors.
 #[derive(Clone, PartialEq, Eq, Debug, Display, From, Error)]
@@ -41,6 +41,10 @@ pub enum VerifyError<E: Error> {
     /// seal lacks witness transaction id information.
     NoWitnessTxid,
 
+    /// seal {0} is not defined over an output of the previous witness
+    /// transaction {1}.
+    UnchainedSeal(Outpoint, Txid),
+
     /// invalid DBC commitment.
     #[display(inner)]
     Dbc(E),
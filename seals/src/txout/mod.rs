@@ -28,8 +28,8 @@ pub mod explicit;
 mod seal;
 mod witness;
 
-pub use blind::{BlindSeal, ChainBlindSeal, SingleBlindSeal};
+pub use blind::{BlindSeal, BlindingFactor, BlindingScheme, ChainBlindSeal, SingleBlindSeal};
 pub use error::{VerifyError, WitnessVoutError};
 pub use explicit::ExplicitSeal;
 pub use seal::{CloseMethod, SealTxid, TxPtr, TxoSeal};
-pub use witness::Witness;
+pub use witness::{verify_seal_chain, Witness};
@@ -21,7 +21,7 @@
 
 use std::marker::PhantomData;
 
-use bc::{Tx, Txid};
+use bc::{Outpoint, Tx, Txid};
 use commit_verify::mpc;
 use dbc::{DbcMethod, Method};
 use single_use_seals::SealWitness;
@@ -57,17 +57,9 @@ impl<D: dbc::Proof<M>, M: DbcMethod> Witness<D, M> {
             _phantom: default!(),
         }
     }
-}
-
-impl<Seal: TxoSeal<M>, Dbc: dbc::Proof<M>, M: SealCloseMethod> SealWitness<Seal>
-    for Witness<Dbc, M>
-{
-    type Message = mpc::Commitment;
-    type Error = VerifyError<Dbc::Error>;
 
-    fn verify_seal(&self, seal: &Seal, msg: &Self::Message) -> Result<(), Self::Error> {
-        // 1. The seal must match tx inputs
-        let outpoint = seal.outpoint().ok_or(VerifyError::NoWitnessTxid)?;
+    /// Checks that the witness transaction has an input spending `outpoint`.
+    fn verify_spending(&self, outpoint: Outpoint) -> Result<(), VerifyError<D::Error>> {
         if !self
             .tx
             .inputs
@@ -76,10 +68,34 @@ impl<Seal: TxoSeal<M>, Dbc: dbc::Proof<M>, M: SealCloseMethod> SealWitness<Seal>
         {
             return Err(VerifyError::WitnessNotClosingSeal(outpoint));
         }
+        Ok(())
+    }
+
+    /// Checks that the witness transaction spends `outpoint` and that the
+    /// witness DBC proof verifies commitment to `msg`.
+    fn verify_closing(
+        &self,
+        outpoint: Outpoint,
+        msg: &mpc::Commitment,
+    ) -> Result<(), VerifyError<D::Error>> {
+        // 1. The seal must match tx inputs
+        self.verify_spending(outpoint)?;
 
-        // 2. Verify DBC with the giving closing method
+        // 2. Verify DBC using the witness proof
         self.proof.verify(msg, &self.tx).map_err(VerifyError::Dbc)
     }
+}
+
+impl<Seal: TxoSeal<M>, Dbc: dbc::Proof<M>, M: SealCloseMethod> SealWitness<Seal>
+    for Witness<Dbc, M>
+{
+    type Message = mpc::Commitment;
+    type Error = VerifyError<Dbc::Error>;
+
+    fn verify_seal(&self, seal: &Seal, msg: &Self::Message) -> Result<(), Self::Error> {
+        let outpoint = seal.outpoint().ok_or(VerifyError::NoWitnessTxid)?;
+        self.verify_closing(outpoint, msg)
+    }
 
     fn verify_many_seals<'seal>(
         &self,
@@ -102,17 +118,142 @@ impl<Seal: TxoSeal<M>, Dbc: dbc::Proof<M>, M: SealCloseMethod> SealWitness<Seal>
 
             // 2. Each seal must match tx inputs
             let outpoint = seal.outpoint().ok_or(VerifyError::NoWitnessTxid)?;
-            if !self
-                .tx
-                .inputs
-                .iter()
-                .any(|txin| txin.prev_output == outpoint)
-            {
-                return Err(VerifyError::WitnessNotClosingSeal(outpoint));
-            }
+            self.verify_spending(outpoint)?;
         }
 
-        // 3. Verify DBC with the giving closing method
+        // 3. Verify DBC with the given closing method
         self.proof.verify(msg, &self.tx).map_err(VerifyError::Dbc)
     }
 }
+
+/// Verifies a chain of seal closings, where each seal after the first one
+/// must be defined over an output of the witness transaction closing the
+/// previous seal. Seals pointing to [`crate::txout::TxPtr::WitnessTx`] are
+/// resolved using the txid of the previous witness, which is computed from
+/// its transaction rather than taken from [`Witness::txid`].
+///
+/// On failure returns position of the first broken closing in the chain
+/// together with the reason.
+pub fn verify_seal_chain<'chain, Seal, D, M>(
+    chain: impl IntoIterator<Item = (&'chain Seal, &'chain mpc::Commitment, &'chain Witness<D, M>)>,
+) -> Result<(), (usize, VerifyError<D::Error>)>
+where
+    Seal: TxoSeal<M> + 'chain,
+    D: dbc::Proof<M> + 'chain,
+    M: SealCloseMethod + 'chain,
+{
+    // Txid and number of outputs of the previous witness transaction
+    let mut prev = None;
+    for (no, (seal, msg, witness)) in chain.into_iter().enumerate() {
+        // 1. The seal must be defined over an output of the previous witness
+        //    transaction
+        let outpoint = match prev {
+            Some((txid, outputs)) => {
+                let outpoint = seal.outpoint_or(txid);
+                if outpoint.txid != txid || outpoint.vout.to_usize() >= outputs {
+                    return Err((no, VerifyError::UnchainedSeal(outpoint, txid)));
+                }
+                outpoint
+            }
+            None => seal
+                .outpoint()
+                .ok_or((no, VerifyError::NoWitnessTxid))?,
+        };
+
+        // 2. The witness must close the seal over the message
+        witness
+            .verify_closing(outpoint, msg)
+            .map_err(|err| (no, err))?;
+
+        prev = Some((witness.tx.txid(), witness.tx.outputs.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use bc::opcodes::OP_RETURN;
+    use bc::{
+        LockTime, Outpoint, ScriptPubkey, SeqNo, SigScript, TxIn, TxOut, TxVer, VarIntArray, Vout,
+        Witness as TxWitness,
+    };
+    use commit_verify::EmbedCommitVerify;
+    use dbc::opret::{OpretFirst, OpretProof};
+
+    use super::*;
+    use crate::txout::{ChainBlindSeal, CloseMethod};
+
+    fn witness(prev_output: Outpoint, msg: &mpc::Commitment) -> Witness<OpretProof> {
+        let mut tx = Tx {
+            version: TxVer::default(),
+            inputs: VarIntArray::from_collection_unsafe(vec![TxIn {
+                prev_output,
+                sig_script: SigScript::default(),
+                sequence: SeqNo::from_consensus_u32(0xFFFF_FFFF),
+                witness: TxWitness::default(),
+            }]),
+            outputs: VarIntArray::from_collection_unsafe(vec![TxOut::new(
+                ScriptPubkey::from_unsafe(vec![OP_RETURN]),
+                0u64,
+            )]),
+            lock_time: LockTime::from_consensus_u32(0),
+        };
+        let proof = <Tx as EmbedCommitVerify<_, OpretFirst>>::embed_commit(&mut tx, msg).unwrap();
+        Witness::with(tx, proof)
+    }
+
+    #[test]
+    fn seal_chain() {
+        let msg1 = mpc::Commitment::from([1u8; 32]);
+        let msg2 = mpc::Commitment::from([2u8; 32]);
+        let genesis = Outpoint::new(Txid::from([0xA5; 32]), 0);
+        let seal1 = ChainBlindSeal::with_blinding(CloseMethod::OpretFirst, genesis.txid, 0u32, 1);
+        let seal2 = ChainBlindSeal::with_blinded_vout(CloseMethod::OpretFirst, 0u32, 2);
+
+        let witness1 = witness(genesis, &msg1);
+        let witness2 = witness(Outpoint::new(witness1.txid, 0), &msg2);
+        verify_seal_chain([(&seal1, &msg1, &witness1), (&seal2, &msg2, &witness2)]).unwrap();
+
+        // Wrong message at the second closing
+        assert!(matches!(
+            verify_seal_chain([(&seal1, &msg1, &witness1), (&seal2, &msg1, &witness2)]),
+            Err((1, VerifyError::Dbc(_)))
+        ));
+
+        // Second seal defined outside of the first witness transaction
+        let unchained =
+            ChainBlindSeal::with_blinding(CloseMethod::OpretFirst, genesis.txid, 0u32, 3);
+        assert!(matches!(
+            verify_seal_chain([(&seal1, &msg1, &witness1), (&unchained, &msg2, &witness2)]),
+            Err((1, VerifyError::UnchainedSeal(outpoint, txid)))
+                if outpoint == genesis && txid == witness1.txid
+        ));
+
+        // Chaining must not trust the public txid field of the witness
+        let mut forged = witness(genesis, &msg1);
+        forged.txid = Txid::from([0x5A; 32]);
+        verify_seal_chain([(&seal1, &msg1, &forged), (&seal2, &msg2, &witness2)]).unwrap();
+        let seal3 = ChainBlindSeal::with_blinding(CloseMethod::OpretFirst, forged.txid, 0u32, 3);
+        let witness4 = witness(Outpoint::new(forged.txid, 0), &msg2);
+        assert!(matches!(
+            verify_seal_chain([(&seal1, &msg1, &forged), (&seal3, &msg2, &witness4)]),
+            Err((1, VerifyError::UnchainedSeal(_, txid))) if txid == witness1.txid
+        ));
+
+        // Second seal defined over a non-existing output of the first witness
+        let beyond = ChainBlindSeal::with_blinded_vout(CloseMethod::OpretFirst, 1u32, 4);
+        let witness5 = witness(Outpoint::new(witness1.txid, Vout::from(1u32)), &msg2);
+        assert!(matches!(
+            verify_seal_chain([(&seal1, &msg1, &witness1), (&beyond, &msg2, &witness5)]),
+            Err((1, VerifyError::UnchainedSeal(outpoint, txid)))
+                if outpoint == Outpoint::new(witness1.txid, 1) && txid == witness1.txid
+        ));
+
+        // Second witness not spending the second seal
+        let witness3 = witness(Outpoint::new(witness1.txid, Vout::from(1u32)), &msg2);
+        assert!(matches!(
+            verify_seal_chain([(&seal1, &msg1, &witness1), (&seal2, &msg2, &witness3)]),
+            Err((1, VerifyError::WitnessNotClosingSeal(_)))
+        ));
+    }
+}